@@ -1,12 +1,15 @@
 #![no_std]
 
+mod autopilot;
+
 use num::Integer;
 use heapless::Vec;
 use oorandom::{self, Rand32};
 use pc_keyboard::{DecodedKey, KeyCode};
 use pluggable_interrupt_os::vga_buffer::{
-    plot, Color, ColorCode, BUFFER_HEIGHT, BUFFER_WIDTH, plot_str, plot_num, clear_row
+    plot, Color, ColorCode, BUFFER_HEIGHT, BUFFER_WIDTH, clear_row
 };
+use autopilot::{NeuralNet, BEST_WEIGHTS};
 
 use core::{
     clone::Clone, cmp::{Eq, PartialEq}, marker::Copy, prelude::rust_2024::derive
@@ -24,6 +27,136 @@ const RMT_LOWER_SPEED: u32 = 2;
 const RMT_UPPER_SPEED: u32 = 5;
 const N_LOWER_SPEED: u32 = 1;
 const N_UPPER_SPEED: u32 = 2;
+const DEFAULT_COLOR_PALETTE: u16 = 0x1FFF;
+const LEVEL_UP_SCORE_INTERVAL: u32 = 10;
+const MIN_SPAWN_RATE: u32 = 2;
+const MAX_UPPER_SPEED: u32 = 10;
+const LEVEL_BANNER_TICKS: u32 = 60;
+const SCORE_TABLE_SIZE: usize = 5;
+const AUTOPILOT_ROW_BAND: usize = 3;
+const HITBOX_SLACK: usize = 1;
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Op {
+    Wait(u32),
+    SetSpawnRate(u32),
+    SetSpeedRange(u32, u32),
+    SpawnBurst(u32),
+    SetColorPalette(u16),
+    Loop
+}
+
+const CAKEWALK_PROGRAM: &[Op] = &[
+    Op::SetSpawnRate(CW_SPAWN_RATE),
+    Op::SetSpeedRange(CW_LOWER_SPEED, CW_UPPER_SPEED),
+    Op::SetColorPalette(DEFAULT_COLOR_PALETTE),
+    Op::Wait(300),
+    Op::SpawnBurst(2),
+    Op::SetSpawnRate(CW_SPAWN_RATE - 1),
+    Op::Wait(300),
+    Op::SpawnBurst(3),
+    Op::Wait(9_000),
+    Op::Loop
+];
+
+const RMT_PROGRAM: &[Op] = &[
+    Op::SetSpawnRate(RMT_SPAWN_RATE),
+    Op::SetSpeedRange(RMT_LOWER_SPEED, RMT_UPPER_SPEED),
+    Op::SetColorPalette(DEFAULT_COLOR_PALETTE),
+    Op::Wait(200),
+    Op::SpawnBurst(3),
+    Op::SetSpawnRate(RMT_SPAWN_RATE - 1),
+    Op::Wait(200),
+    Op::SetSpeedRange(RMT_LOWER_SPEED + 1, RMT_UPPER_SPEED + 1),
+    Op::SpawnBurst(4),
+    Op::Wait(9_000),
+    Op::Loop
+];
+
+const NIGHTMARE_PROGRAM: &[Op] = &[
+    Op::SetSpawnRate(N_SPAWN_RATE),
+    Op::SetSpeedRange(N_LOWER_SPEED, N_UPPER_SPEED),
+    Op::SetColorPalette(DEFAULT_COLOR_PALETTE),
+    Op::Wait(150),
+    Op::SpawnBurst(5),
+    Op::SetSpawnRate(N_SPAWN_RATE - 1),
+    Op::Wait(150),
+    Op::SetSpeedRange(N_LOWER_SPEED, N_UPPER_SPEED + 1),
+    Op::SpawnBurst(6),
+    Op::Wait(9_000),
+    Op::Loop
+];
+
+type Buffer = [[(char, ColorCode); BUFFER_WIDTH]; BUFFER_HEIGHT];
+
+fn blank_cell() -> (char, ColorCode) {
+    (' ', ColorCode::new(Color::Black, Color::Black))
+}
+
+fn blank_buffer() -> Buffer {
+    [[blank_cell(); BUFFER_WIDTH]; BUFFER_HEIGHT]
+}
+
+fn buffer_str(buffer: &mut Buffer, text: &str, col: usize, row: usize, color: ColorCode) {
+    for (i, character) in text.chars().enumerate() {
+        buffer[row][col + i] = (character, color);
+    }
+}
+
+fn buffer_num(buffer: &mut Buffer, num: isize, col: usize, row: usize, color: ColorCode) {
+    let mut digits: [u8; 10] = [0; 10];
+    let mut count = 0;
+    let mut remaining = num.unsigned_abs();
+    loop {
+        digits[count] = (remaining % 10) as u8;
+        count += 1;
+        remaining /= 10;
+        if remaining == 0 {
+            break;
+        }
+    }
+    for i in 0..count {
+        let digit = digits[count - 1 - i];
+        buffer[row][col + i] = ((b'0' + digit) as char, color);
+    }
+}
+
+fn buffer_clear_row(buffer: &mut Buffer, row: usize) {
+    for col in 0..BUFFER_WIDTH {
+        buffer[row][col] = blank_cell();
+    }
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+struct Rect {
+    left: usize,
+    top: usize,
+    right: usize,
+    bottom: usize
+}
+
+impl Rect {
+    fn new(col: usize, row: usize, width: usize, height: usize) -> Self {
+        Self { left: col, top: row, right: col + width, bottom: row + height }
+    }
+}
+
+fn rects_overlap(a: Rect, b: Rect) -> bool {
+    a.left <= b.right && b.left <= a.right && a.top <= b.bottom && b.top <= a.bottom
+}
+
+fn hitbox_around(col: usize, row: usize) -> Rect {
+    Rect::new(
+        col.saturating_sub(HITBOX_SLACK), row.saturating_sub(HITBOX_SLACK), HITBOX_SLACK * 2, HITBOX_SLACK * 2
+    )
+}
+
+const DESTROY_CARET_FRAMES: [(char, Color); 4] = [
+    ('\u{b7}', Color::Yellow), ('+', Color::Yellow), ('*', Color::LightRed), ('\u{d7}', Color::Red)
+];
+const COLLISION_CARET_FRAMES: [(char, Color); 4] = [
+    ('\u{b7}', Color::LightRed), ('+', Color::Red), ('*', Color::Red), ('\u{d7}', Color::Red)
+];
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 enum GameStatus {
@@ -46,18 +179,131 @@ enum DebrisStatus {
     Destroy
 }
 
+#[derive(Copy, Clone, Eq, PartialEq)]
+struct ScoreEntry {
+    initials: [char; 3],
+    score: u32
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+struct InitialsEntry {
+    initials: [char; 3],
+    cursor: usize
+}
+
+fn insert_score(table: &mut Vec<ScoreEntry, SCORE_TABLE_SIZE>, entry: ScoreEntry) {
+    let mut index = table.len();
+    for (i, existing) in table.iter().enumerate() {
+        if entry.score > existing.score {
+            index = i;
+            break;
+        }
+    }
+    if index >= SCORE_TABLE_SIZE {
+        return;
+    }
+    if table.len() == SCORE_TABLE_SIZE {
+        let _ = table.pop();
+    }
+    let _ = table.insert(index, entry);
+}
+
+fn next_letter(letter: char) -> char {
+    if letter >= 'Z' {
+        'A'
+    } else {
+        ((letter as u8) + 1) as char
+    }
+}
+
+fn prev_letter(letter: char) -> char {
+    if letter <= 'A' {
+        'Z'
+    } else {
+        ((letter as u8) - 1) as char
+    }
+}
+
+fn draw_score_table(buffer: &mut Buffer, label: &str, table: &Vec<ScoreEntry, SCORE_TABLE_SIZE>, col: usize, row: usize) {
+    let header_color: ColorCode = ColorCode::new(Color::White, Color::Black);
+    let entry_color: ColorCode = ColorCode::new(Color::LightGray, Color::Black);
+    buffer_str(buffer, label, col, row, header_color);
+    for i in 0..SCORE_TABLE_SIZE {
+        if let Some(entry) = table.get(i) {
+            let initials: [char; 3] = entry.initials;
+            buffer[row + 1 + i][col] = (initials[0], entry_color);
+            buffer[row + 1 + i][col + 1] = (initials[1], entry_color);
+            buffer[row + 1 + i][col + 2] = (initials[2], entry_color);
+            buffer_num(buffer, entry.score as isize, col + 4, row + 1 + i, entry_color);
+        }
+    }
+}
+
+fn draw_initials_entry(buffer: &mut Buffer, entry: InitialsEntry) {
+    let prompt_color: ColorCode = ColorCode::new(Color::Yellow, Color::Black);
+    let selected_color: ColorCode = ColorCode::new(Color::Black, Color::Yellow);
+    let prompt_text: &str = "NEW HIGH SCORE! Enter initials (Up/Down, Enter):";
+    let prompt_row = BUFFER_HEIGHT / 2 - 2;
+    let prompt_col = BUFFER_WIDTH / 2 - prompt_text.len() / 2;
+    buffer_clear_row(buffer, prompt_row);
+    buffer_clear_row(buffer, prompt_row + 1);
+    buffer_str(buffer, prompt_text, prompt_col, prompt_row, prompt_color);
+    let letters_col = BUFFER_WIDTH / 2 - 1;
+    for i in 0..3 {
+        let color = if i == entry.cursor { selected_color } else { prompt_color };
+        buffer[prompt_row + 1][letters_col + i] = (entry.initials[i], color);
+    }
+}
+
+fn draw_title_screen(
+    buffer: &mut Buffer,
+    cw_scores: &Vec<ScoreEntry, SCORE_TABLE_SIZE>,
+    rmt_scores: &Vec<ScoreEntry, SCORE_TABLE_SIZE>,
+    n_scores: &Vec<ScoreEntry, SCORE_TABLE_SIZE>,
+    initials_entry: Option<InitialsEntry>
+) {
+    let color_white: ColorCode = ColorCode::new(Color::White, Color::Black);
+    let color_red: ColorCode = ColorCode::new(Color::LightRed, Color::Black);
+    let title_text: &str = "SPACE JUNK";
+    let control_text: &str = "Controls: Up/Down Arrow Keys, Spacebar to Shoot";
+    let difficulty_text: &str = "Press 1 to Play Cakewalk, 2 for Road Most Travelled, 3 for Nightmare";
+    buffer_str(buffer, title_text, BUFFER_WIDTH / 2 - 5, 2, color_red);
+    draw_score_table(buffer, "Cakewalk", cw_scores, BUFFER_WIDTH / 2 - 36, 4);
+    draw_score_table(buffer, "Road Most Travelled", rmt_scores, BUFFER_WIDTH / 2 - 10, 4);
+    draw_score_table(buffer, "Nightmare", n_scores, BUFFER_WIDTH / 2 + 22, 4);
+    buffer_str(buffer, control_text, BUFFER_WIDTH / 2 - 18, BUFFER_HEIGHT - 4, color_white);
+    buffer_str(buffer, difficulty_text, BUFFER_WIDTH / 2 - 34, BUFFER_HEIGHT - 3, color_white);
+    if let Some(entry) = initials_entry {
+        draw_initials_entry(buffer, entry);
+    }
+}
+
 #[derive(Clone, Eq, PartialEq)]
 pub struct SpaceDebrisGame {
     player: Player,
     debris: Vec<Debris, 50>,
+    projectiles: Vec<Projectile, 16>,
+    carets: Vec<Caret, 32>,
     score: u32,
-    cw_high_score: u32,
-    rmt_high_score: u32,
-    n_high_score: u32,
+    cw_scores: Vec<ScoreEntry, SCORE_TABLE_SIZE>,
+    rmt_scores: Vec<ScoreEntry, SCORE_TABLE_SIZE>,
+    n_scores: Vec<ScoreEntry, SCORE_TABLE_SIZE>,
+    initials_entry: Option<InitialsEntry>,
     spawn_countdown: u32,
     spawn_rate: u32,
     seed_count: u32,
-    difficulty: Difficulty
+    level: u32,
+    level_banner_ticks: u32,
+    difficulty: Difficulty,
+    program: &'static [Op],
+    pc: usize,
+    op_wait: u32,
+    lower_speed: u32,
+    upper_speed: u32,
+    color_mask: u16,
+    front_buffer: Buffer,
+    back_buffer: Buffer,
+    demo_active: bool
 }
 
 #[derive(Copy, Clone, Eq, PartialEq)]
@@ -78,6 +324,27 @@ pub struct Debris {
     debris_status: DebrisStatus
 }
 
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct Projectile {
+    col: usize,
+    row: usize,
+    dx_tick: usize
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum CaretKind {
+    DebrisDestroyed,
+    PlayerCollision
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub struct Caret {
+    col: usize,
+    row: usize,
+    frame: usize,
+    kind: CaretKind
+}
+
 fn safe_add<const LIMIT: usize>(a: usize, b: usize) -> usize {
     (a + b).mod_floor(&LIMIT)
 }
@@ -93,16 +360,35 @@ fn sub1<const LIMIT: usize>(value: usize) -> usize {
 impl Default for SpaceDebrisGame {
     fn default() -> Self {
         Self {
-            player: Player::default(),
+            player: Player {
+                col: BUFFER_WIDTH / 4,
+                row: BUFFER_HEIGHT / 2,
+                dy: 0,
+                game_status: GameStatus::GameRunning
+            },
             debris: Vec::new(),
+            projectiles: Vec::new(),
+            carets: Vec::new(),
             score: 0,
-            cw_high_score: 0,
-            rmt_high_score: 0,
-            n_high_score: 0,
+            cw_scores: Vec::new(),
+            rmt_scores: Vec::new(),
+            n_scores: Vec::new(),
+            initials_entry: None,
             spawn_countdown: 0,
-            spawn_rate: 0,
+            spawn_rate: CW_SPAWN_RATE,
             seed_count: 0,
-            difficulty: Difficulty::Undefined
+            level: 0,
+            level_banner_ticks: 0,
+            difficulty: Difficulty::Undefined,
+            program: &[],
+            pc: 0,
+            op_wait: 0,
+            lower_speed: 1,
+            upper_speed: 5,
+            color_mask: DEFAULT_COLOR_PALETTE,
+            front_buffer: blank_buffer(),
+            back_buffer: blank_buffer(),
+            demo_active: true
         }
     }
 }
@@ -119,38 +405,74 @@ impl Default for Player {
 }
 
 impl Debris {
-    fn new(num: u32, lower_speed: u32, upper_speed: u32) -> Self {
+    fn new(num: u32, lower_speed: u32, upper_speed: u32, color_mask: u16) -> Self {
         let mut rng: Rand32 = Rand32::new(num.into());
+        let mut palette: [Color; 13] = DEBRIS_COLORS;
+        let mut palette_size = 0;
+        for (i, &color) in DEBRIS_COLORS.iter().enumerate() {
+            if color_mask & (1 << i) != 0 {
+                palette[palette_size] = color;
+                palette_size += 1;
+            }
+        }
+        if palette_size == 0 {
+            palette[0] = Color::White;
+            palette_size = 1;
+        }
         Self {
             col: BUFFER_WIDTH - 1,
             row: rng.rand_range(2..BUFFER_HEIGHT as u32) as usize,
             dx: rng.rand_range(lower_speed..upper_speed) as usize,
             dx_tick: 0,
-            color: DEBRIS_COLORS[rng.rand_range(0..13) as usize],
+            color: palette[rng.rand_range(0..palette_size as u32) as usize],
             debris_status: DebrisStatus::Normal
         }
     }
+
+    fn hitbox(&self) -> Rect {
+        hitbox_around(self.col, self.row)
+    }
+}
+
+impl Projectile {
+    fn new(col: usize, row: usize) -> Self {
+        Self { col: add1::<BUFFER_WIDTH>(col), row, dx_tick: 0 }
+    }
+
+    fn hitbox(&self) -> Rect {
+        hitbox_around(self.col, self.row)
+    }
 }
 
 impl SpaceDebrisGame {
     pub fn update(&mut self) {
+        if self.demo_active {
+            self.update_demo();
+            return;
+        }
         self.seed_count += 1;
-        if let Some(event) = self.player.tick() {
+        let running_before_tick = self.player.game_status == GameStatus::GameRunning;
+        if let Some(event) = self.player.tick(&mut self.back_buffer) {
             match event {
                 GameStatus::GameRunning => {},
                 GameStatus::GameStopped => {
                     self.player.dy = 0;
-                    self.update_high_score();
-                    self.display_title_screen();
+                    if running_before_tick {
+                        self.begin_game_over();
+                    }
                 }
             }
         }
+        self.update_projectiles();
+        let running_before_debris = self.player.game_status == GameStatus::GameRunning;
         let mut deleted_debris: Vec<usize, 50> = Vec::<usize, 50>::new();
         for i in 0..self.debris.len() {
-            if let Some(event) = self.debris[i].tick(&mut self.player) {
+            if let Some(event) = self.debris[i].tick(&mut self.player, &mut self.back_buffer) {
                 match event {
                     DebrisStatus::ScorePoint => self.increment_score(),
                     DebrisStatus::Destroy => {
+                        let (col, row) = (self.debris[i].col, self.debris[i].row);
+                        let _ = self.carets.push(Caret::new(col, row, CaretKind::DebrisDestroyed));
                         let _ = deleted_debris.push(i);
                     },
                     DebrisStatus::Normal => {}
@@ -160,30 +482,94 @@ impl SpaceDebrisGame {
         for &debris in deleted_debris.iter().rev() {
             self.debris.remove(debris);
         }
+        if running_before_debris && self.player.game_status == GameStatus::GameStopped {
+            let _ = self.carets.push(Caret::new(self.player.col, self.player.row, CaretKind::PlayerCollision));
+        }
+        self.update_carets();
+        self.display_level_banner();
+        self.run_script();
         self.create_debris();
+        self.render();
+    }
+
+    fn update_carets(&mut self) {
+        let mut spent_carets: Vec<usize, 32> = Vec::<usize, 32>::new();
+        for i in 0..self.carets.len() {
+            if !self.carets[i].tick(&mut self.back_buffer) {
+                let _ = spent_carets.push(i);
+            }
+        }
+        for &i in spent_carets.iter().rev() {
+            self.carets.remove(i);
+        }
+    }
+
+    fn update_projectiles(&mut self) {
+        let mut spent_projectiles: Vec<usize, 16> = Vec::<usize, 16>::new();
+        for i in 0..self.projectiles.len() {
+            if !self.projectiles[i].tick(&mut self.back_buffer) {
+                let _ = spent_projectiles.push(i);
+            }
+        }
+        for i in 0..self.projectiles.len() {
+            if spent_projectiles.contains(&i) {
+                continue;
+            }
+            let projectile_rect = self.projectiles[i].hitbox();
+            for d in 0..self.debris.len() {
+                if self.debris[d].debris_status == DebrisStatus::Destroy {
+                    continue;
+                }
+                if rects_overlap(projectile_rect, self.debris[d].hitbox()) {
+                    self.debris[d].debris_status = DebrisStatus::Destroy;
+                    let _ = spent_projectiles.push(i);
+                    self.increment_score();
+                    break;
+                }
+            }
+        }
+        spent_projectiles.sort_unstable();
+        for &i in spent_projectiles.iter().rev() {
+            self.projectiles.remove(i);
+        }
+    }
+
+    fn render(&mut self) {
+        for row in 0..BUFFER_HEIGHT {
+            for col in 0..BUFFER_WIDTH {
+                let back_cell = self.back_buffer[row][col];
+                if back_cell != self.front_buffer[row][col] {
+                    let (character, color_code) = back_cell;
+                    plot(character, col, row, color_code);
+                }
+            }
+        }
+        core::mem::swap(&mut self.front_buffer, &mut self.back_buffer);
+    }
+
+    pub fn display_title_screen(&mut self) {
+        self.draw_title_screen_to_back_buffer();
+        self.render();
+    }
+
+    // Stages the title screen into back_buffer without flushing; tick callers rely on their own trailing render().
+    fn draw_title_screen_to_back_buffer(&mut self) {
+        draw_title_screen(&mut self.back_buffer, &self.cw_scores, &self.rmt_scores, &self.n_scores, self.initials_entry);
     }
 
-    pub fn display_title_screen(&self) {
-        let color_white: ColorCode = ColorCode::new(Color::White, Color::Black);
-        let color_red: ColorCode = ColorCode::new(Color::LightRed, Color::Black);
-        let title_text: &str = "SPACE JUNK";
-        let cw_score_text: &str = "High Score (Cakewalk): ";
-        let rmt_score_text: &str = "High Score (Road Most Travelled): ";
-        let n_score_text: &str = "High Score (Nightmare): ";
-        let control_text: &str = "Controls: Up/Down Arrow Keys";
-        let difficulty_text: &str = "Press 1 to Play Cakewalk, 2 for Road Most Travelled, 3 for Nightmare";
-        plot_str(title_text, BUFFER_WIDTH / 2 - 5, BUFFER_HEIGHT / 2 - 4, color_red);
-        plot_str(cw_score_text, BUFFER_WIDTH / 2 - 12, BUFFER_HEIGHT / 2 - 2, color_white);
-        plot_num(self.cw_high_score as isize, cw_score_text.len() + 28, BUFFER_HEIGHT / 2 - 2, color_white);
-        plot_str(rmt_score_text, BUFFER_WIDTH / 2 - 18, BUFFER_HEIGHT / 2 - 1, color_white);
-        plot_num(self.rmt_high_score as isize, rmt_score_text.len() + 22, BUFFER_HEIGHT / 2 - 1, color_white);
-        plot_str(n_score_text, BUFFER_WIDTH / 2 - 12, BUFFER_HEIGHT / 2, color_white);
-        plot_num(self.n_high_score as isize, n_score_text.len() + 28, BUFFER_HEIGHT / 2, color_white);
-        plot_str(control_text, BUFFER_WIDTH / 2 - 13, BUFFER_HEIGHT / 2 + 2, color_white);
-        plot_str(difficulty_text, BUFFER_WIDTH / 2 - 34, BUFFER_HEIGHT / 2 + 3, color_white);
+    fn display_initials_entry(&mut self, entry: InitialsEntry) {
+        draw_initials_entry(&mut self.back_buffer, entry);
+        self.render();
     }
 
     pub fn key(&mut self, key: DecodedKey) {
+        if let Some(entry) = self.initials_entry {
+            self.handle_initials_key(entry, key);
+            return;
+        }
+        if self.demo_active {
+            self.end_demo();
+        }
         if self.player.game_status == GameStatus::GameStopped {
             if key == DecodedKey::Unicode('1') {
                 self.difficulty = Difficulty::Cakewalk;
@@ -196,38 +582,111 @@ impl SpaceDebrisGame {
                 self.reset();
             }
         }
-        self.player.key(key);
+        if let Some(projectile) = self.player.key(key) {
+            let _ = self.projectiles.push(projectile);
+        }
+    }
+
+    fn handle_initials_key(&mut self, mut entry: InitialsEntry, key: DecodedKey) {
+        match key {
+            DecodedKey::RawKey(KeyCode::ArrowUp) => {
+                entry.initials[entry.cursor] = next_letter(entry.initials[entry.cursor]);
+                self.initials_entry = Some(entry);
+                self.display_initials_entry(entry);
+            },
+            DecodedKey::RawKey(KeyCode::ArrowDown) => {
+                entry.initials[entry.cursor] = prev_letter(entry.initials[entry.cursor]);
+                self.initials_entry = Some(entry);
+                self.display_initials_entry(entry);
+            },
+            DecodedKey::Unicode('\n') | DecodedKey::Unicode('\r') => {
+                if entry.cursor + 1 < entry.initials.len() {
+                    entry.cursor += 1;
+                    self.initials_entry = Some(entry);
+                    self.display_initials_entry(entry);
+                } else {
+                    self.submit_initials(entry.initials);
+                    self.initials_entry = None;
+                    self.demo_active = true;
+                    self.display_title_screen();
+                }
+            },
+            _ => {}
+        }
     }
 
     fn increment_score(&mut self) {
         if self.player.game_status == GameStatus::GameRunning {
             self.score += 1;
+            if self.score % LEVEL_UP_SCORE_INTERVAL == 0 {
+                self.level_up();
+            }
             self.display_score();
         }
     }
 
-    fn update_high_score(&mut self) {
+    fn level_up(&mut self) {
+        self.level += 1;
+        self.level_banner_ticks = LEVEL_BANNER_TICKS;
+    }
+
+    fn effective_spawn_rate(&self) -> u32 {
+        self.spawn_rate.saturating_sub(self.level).max(MIN_SPAWN_RATE)
+    }
+
+    fn effective_speed_range(&self) -> (u32, u32) {
+        (self.lower_speed, (self.upper_speed + self.level).min(MAX_UPPER_SPEED))
+    }
+
+    fn display_level_banner(&mut self) {
+        if self.level_banner_ticks == 0 {
+            return;
+        }
+        self.level_banner_ticks -= 1;
+        if self.level_banner_ticks == 0 {
+            buffer_clear_row(&mut self.back_buffer, 1);
+            return;
+        }
+        let banner_color = ColorCode::new(Color::Yellow, Color::Black);
+        let banner_text: &str = "LEVEL ";
+        let banner_col = BUFFER_WIDTH / 2 - 3;
+        buffer_clear_row(&mut self.back_buffer, 1);
+        buffer_str(&mut self.back_buffer, banner_text, banner_col, 1, banner_color);
+        buffer_num(&mut self.back_buffer, self.level as isize, banner_col + banner_text.len(), 1, banner_color);
+    }
+
+    fn high_score_table(&self) -> &Vec<ScoreEntry, SCORE_TABLE_SIZE> {
+        match self.difficulty {
+            Difficulty::Undefined | Difficulty::Cakewalk => &self.cw_scores,
+            Difficulty::RMT => &self.rmt_scores,
+            Difficulty::Nightmare => &self.n_scores
+        }
+    }
+
+    fn begin_game_over(&mut self) {
+        let table = self.high_score_table();
+        let qualifies = self.score > 0
+            && (table.len() < SCORE_TABLE_SIZE || self.score > table[table.len() - 1].score);
+        if qualifies {
+            self.initials_entry = Some(InitialsEntry { initials: ['A', 'A', 'A'], cursor: 0 });
+        } else {
+            // No initials to collect, so the attract-mode demo can resume right away.
+            self.demo_active = true;
+        }
+        self.draw_title_screen_to_back_buffer();
+    }
+
+    fn submit_initials(&mut self, initials: [char; 3]) {
+        let entry = ScoreEntry { initials, score: self.score };
         match self.difficulty {
             Difficulty::Undefined => {},
-            Difficulty::Cakewalk => {
-                if self.score > self.cw_high_score {
-                    self.cw_high_score = self.score;
-                }
-            },
-            Difficulty::RMT => {
-                if self.score > self.rmt_high_score {
-                    self.rmt_high_score = self.score;
-                }
-            },
-            Difficulty::Nightmare => {
-                if self.score > self.n_high_score {
-                    self.n_high_score = self.score;
-                }
-            }
+            Difficulty::Cakewalk => insert_score(&mut self.cw_scores, entry),
+            Difficulty::RMT => insert_score(&mut self.rmt_scores, entry),
+            Difficulty::Nightmare => insert_score(&mut self.n_scores, entry)
         }
     }
 
-    fn display_score(&self) {
+    fn display_score(&mut self) {
         let header_color: ColorCode = ColorCode::new(Color::White, Color::Black);
         let mut score_text: &str = "";
         match self.difficulty {
@@ -236,78 +695,226 @@ impl SpaceDebrisGame {
             Difficulty::RMT => score_text = "Score (Road Most Travelled): ",
             Difficulty::Nightmare => score_text = "Score (Nightmare): "
         }
-        clear_row(0, Color::Black);
-        plot_str(score_text, 0, 0, header_color);
-        plot_num(self.score as isize, score_text.len(), 0, header_color);
+        buffer_clear_row(&mut self.back_buffer, 0);
+        buffer_str(&mut self.back_buffer, score_text, 0, 0, header_color);
+        buffer_num(&mut self.back_buffer, self.score as isize, score_text.len(), 0, header_color);
+        let level_text: &str = "Level: ";
+        let level_col = BUFFER_WIDTH - 12;
+        buffer_str(&mut self.back_buffer, level_text, level_col, 0, header_color);
+        buffer_num(&mut self.back_buffer, self.level as isize, level_col + level_text.len(), 0, header_color);
     }
 
-    fn create_debris(&mut self) {
-        self.seed_count += 1;
-        if self.spawn_countdown == 0 {
-            let mut lower_speed: u32 = 1;
-            let mut upper_speed: u32 = 5;
-            match self.difficulty {
-                Difficulty::Undefined => {},
-                Difficulty::Cakewalk => {
-                    lower_speed = CW_LOWER_SPEED;
-                    upper_speed = CW_UPPER_SPEED;
+    fn run_script(&mut self) {
+        if self.program.is_empty() {
+            return;
+        }
+        if self.op_wait > 0 {
+            self.op_wait -= 1;
+            return;
+        }
+        // Caps iterations so a Wait-less program can't spin forever and hang cpu_loop.
+        for _ in 0..=self.program.len() {
+            if self.pc >= self.program.len() {
+                self.pc = 0;
+            }
+            match self.program[self.pc] {
+                Op::Wait(ticks) => {
+                    self.op_wait = ticks;
+                    self.pc += 1;
+                    break;
+                },
+                Op::SetSpawnRate(rate) => {
+                    self.spawn_rate = rate;
+                    self.pc += 1;
+                },
+                Op::SetSpeedRange(lower, upper) => {
+                    self.lower_speed = lower;
+                    self.upper_speed = upper;
+                    self.pc += 1;
                 },
-                Difficulty::RMT => {
-                    lower_speed = RMT_LOWER_SPEED;
-                    upper_speed = RMT_UPPER_SPEED;
+                Op::SpawnBurst(count) => {
+                    let (lower_speed, upper_speed) = self.effective_speed_range();
+                    for _ in 0..count {
+                        self.seed_count += 1;
+                        let _ = self.debris.push(Debris::new(
+                            self.seed_count, lower_speed, upper_speed, self.color_mask
+                        ));
+                    }
+                    self.pc += 1;
                 },
-                Difficulty::Nightmare => {
-                    lower_speed = N_LOWER_SPEED;
-                    upper_speed = N_UPPER_SPEED;
+                Op::SetColorPalette(mask) => {
+                    self.color_mask = mask;
+                    self.pc += 1;
+                },
+                Op::Loop => {
+                    self.pc = 0;
                 }
             }
-            let _ = self.debris.push(Debris::new(self.seed_count, lower_speed, upper_speed));
-            self.spawn_countdown = self.spawn_rate;
+        }
+    }
+
+    fn create_debris(&mut self) {
+        self.seed_count += 1;
+        if self.spawn_countdown == 0 {
+            let (lower_speed, upper_speed) = self.effective_speed_range();
+            let _ = self.debris.push(Debris::new(
+                self.seed_count, lower_speed, upper_speed, self.color_mask
+            ));
+            self.spawn_countdown = self.effective_spawn_rate();
         } else {
             self.spawn_countdown -= 1;
         }
     }
 
     fn reset(&mut self) {
+        buffer_clear_row(&mut self.back_buffer, 1);
         for i in 8..=15 {
-            clear_row(i, Color::Black);
+            buffer_clear_row(&mut self.back_buffer, i);
         }
         self.player.game_status = GameStatus::GameRunning;
-        match self.difficulty {
-            Difficulty::Undefined => {},
-            Difficulty::Cakewalk => self.spawn_rate = CW_SPAWN_RATE,
-            Difficulty::RMT => self.spawn_rate = RMT_SPAWN_RATE,
-            Difficulty::Nightmare => self.spawn_rate = N_SPAWN_RATE
-        }
-        self.player.clear_current();
+        self.program = match self.difficulty {
+            Difficulty::Undefined => &[],
+            Difficulty::Cakewalk => CAKEWALK_PROGRAM,
+            Difficulty::RMT => RMT_PROGRAM,
+            Difficulty::Nightmare => NIGHTMARE_PROGRAM
+        };
+        self.pc = 0;
+        self.op_wait = 0;
+        self.lower_speed = 1;
+        self.upper_speed = 5;
+        self.color_mask = DEFAULT_COLOR_PALETTE;
+        self.player.clear_current(&mut self.back_buffer);
         let mut deleted_debris: Vec<usize, 50> = Vec::<usize, 50>::new();
         for i in 0..self.debris.len() {
             let _ = deleted_debris.push(i);
-            self.debris[i].clear_current();
+            self.debris[i].clear_current(&mut self.back_buffer);
         }
         for &debris in deleted_debris.iter().rev() {
             self.debris.remove(debris);
         }
+        for i in 0..self.projectiles.len() {
+            self.projectiles[i].clear_current(&mut self.back_buffer);
+        }
+        self.projectiles.clear();
+        for i in 0..self.carets.len() {
+            self.carets[i].clear_current(&mut self.back_buffer);
+        }
+        self.carets.clear();
         self.score = 0;
+        self.level = 0;
+        self.level_banner_ticks = 0;
         self.player.row = BUFFER_HEIGHT / 2;
         self.player.col = BUFFER_WIDTH / 4;
         self.display_score();
+        self.render();
+    }
+
+    fn update_demo(&mut self) {
+        self.seed_count += 1;
+        // Restage the title screen into back_buffer every tick so crossing debris can't erase it.
+        self.draw_title_screen_to_back_buffer();
+        self.player.dy = self.autopilot_move();
+        let running_before_tick = self.player.game_status == GameStatus::GameRunning;
+        self.player.tick(&mut self.back_buffer);
+        let mut deleted_debris: Vec<usize, 50> = Vec::<usize, 50>::new();
+        for i in 0..self.debris.len() {
+            if let Some(DebrisStatus::Destroy) = self.debris[i].tick(&mut self.player, &mut self.back_buffer) {
+                let (col, row) = (self.debris[i].col, self.debris[i].row);
+                let _ = self.carets.push(Caret::new(col, row, CaretKind::DebrisDestroyed));
+                let _ = deleted_debris.push(i);
+            }
+        }
+        for &debris in deleted_debris.iter().rev() {
+            self.debris.remove(debris);
+        }
+        if running_before_tick && self.player.game_status == GameStatus::GameStopped {
+            let _ = self.carets.push(Caret::new(self.player.col, self.player.row, CaretKind::PlayerCollision));
+        }
+        self.update_carets();
+        if self.player.game_status == GameStatus::GameStopped {
+            self.start_demo();
+        }
+        self.create_debris();
+        self.render();
+    }
+
+    fn autopilot_move(&self) -> isize {
+        let mut nearest: Option<(f32, f32)> = None;
+        let mut second_nearest: Option<(f32, f32)> = None;
+        for debris in self.debris.iter() {
+            if debris.col < self.player.col {
+                continue;
+            }
+            let row_distance = debris.row.abs_diff(self.player.row);
+            if row_distance > AUTOPILOT_ROW_BAND {
+                continue;
+            }
+            let candidate = (
+                debris.row as f32 - self.player.row as f32,
+                (debris.col - self.player.col) as f32
+            );
+            match nearest {
+                None => nearest = Some(candidate),
+                Some(closest) if candidate.1 < closest.1 => {
+                    second_nearest = nearest;
+                    nearest = Some(candidate);
+                },
+                _ => match second_nearest {
+                    None => second_nearest = Some(candidate),
+                    Some(next_closest) if candidate.1 < next_closest.1 => second_nearest = Some(candidate),
+                    _ => {}
+                }
+            }
+        }
+        let far_away = (0.0, BUFFER_WIDTH as f32);
+        let (row_offset_1, col_distance_1) = nearest.unwrap_or(far_away);
+        let (row_offset_2, col_distance_2) = second_nearest.unwrap_or(far_away);
+        let pilot = NeuralNet::from_weights(&BEST_WEIGHTS);
+        pilot.decide([row_offset_1, col_distance_1, row_offset_2, col_distance_2])
+    }
+
+    fn start_demo(&mut self) {
+        self.demo_active = true;
+        self.player.game_status = GameStatus::GameRunning;
+        self.player.row = BUFFER_HEIGHT / 2;
+        self.player.col = BUFFER_WIDTH / 4;
+        self.spawn_rate = CW_SPAWN_RATE;
+        self.spawn_countdown = 0;
+    }
+
+    fn end_demo(&mut self) {
+        self.demo_active = false;
+        self.player.game_status = GameStatus::GameStopped;
+        self.player.clear_current(&mut self.back_buffer);
+        let mut deleted_debris: Vec<usize, 50> = Vec::<usize, 50>::new();
+        for i in 0..self.debris.len() {
+            let _ = deleted_debris.push(i);
+            self.debris[i].clear_current(&mut self.back_buffer);
+        }
+        for &debris in deleted_debris.iter().rev() {
+            self.debris.remove(debris);
+        }
+        for i in 0..self.carets.len() {
+            self.carets[i].clear_current(&mut self.back_buffer);
+        }
+        self.carets.clear();
+        self.render();
     }
 }
 
 impl Player {
-    fn tick(&mut self) -> Option<GameStatus> {
-        self.clear_current();
+    fn tick(&mut self, buffer: &mut Buffer) -> Option<GameStatus> {
+        self.clear_current(buffer);
         self.update_location();
-        self.draw_current();
+        self.draw_current(buffer);
         if self.game_status == GameStatus::GameStopped {
             return Some(GameStatus::GameStopped);
         }
         Some(GameStatus::GameRunning)
     }
 
-    fn clear_current(&self) {
-        plot(' ', self.col, self.row, ColorCode::new(Color::Black, Color::Black));
+    fn clear_current(&self, buffer: &mut Buffer) {
+        buffer[self.row][self.col] = blank_cell();
     }
 
     fn update_location(&mut self) {
@@ -323,24 +930,28 @@ impl Player {
         self.game_status = GameStatus::GameStopped;
     }
 
-    fn draw_current(&self) {
+    fn draw_current(&self, buffer: &mut Buffer) {
         if self.game_status == GameStatus::GameRunning {
-            plot(
-                '>',
-                self.col,
-                self.row,
-                ColorCode::new(Color::White, Color::Black),
-            );
+            buffer[self.row][self.col] = ('>', ColorCode::new(Color::White, Color::Black));
         }
     }
 
-    fn key(&mut self, key: DecodedKey) {
+    fn hitbox(&self) -> Rect {
+        hitbox_around(self.col, self.row)
+    }
+
+    fn key(&mut self, key: DecodedKey) -> Option<Projectile> {
         if let DecodedKey::RawKey(code) = key {
-            self.handle_raw(code);
+            return self.handle_raw(code);
+        }
+        // pc_keyboard decodes the space key as a character, not as a raw key code.
+        if key == DecodedKey::Unicode(' ') {
+            return self.handle_raw(KeyCode::Spacebar);
         }
+        None
     }
 
-    fn handle_raw(&mut self, key: KeyCode) {
+    fn handle_raw(&mut self, key: KeyCode) -> Option<Projectile> {
         if self.game_status == GameStatus::GameRunning {
             match key {
                 KeyCode::ArrowUp => {
@@ -349,36 +960,44 @@ impl Player {
                 KeyCode::ArrowDown => {
                     self.dy = 1;
                 },
+                KeyCode::Spacebar => {
+                    return Some(Projectile::new(self.col, self.row));
+                },
                 _ => {}
             }
         }
+        None
     }
 }
 
 impl Debris {
-    fn tick(&mut self, player: &mut Player) -> Option<DebrisStatus> {
+    fn tick(&mut self, player: &mut Player, buffer: &mut Buffer) -> Option<DebrisStatus> {
         if player.game_status == GameStatus::GameRunning {
-            self.clear_current();
+            if self.debris_status == DebrisStatus::Destroy {
+                self.clear_current(buffer);
+                return Some(DebrisStatus::Destroy);
+            }
+            self.clear_current(buffer);
             self.update_location();
-            if self.col == player.col && self.row == player.row {
+            if rects_overlap(self.hitbox(), player.hitbox()) {
                 player.collide();
             }
-            self.draw_current(*player);
+            self.draw_current(*player, buffer);
             if self.col == 18 {
                 return Some(DebrisStatus::ScorePoint);
             }
             if self.col == 0 {
-                self.clear_current();
+                self.clear_current(buffer);
                 return Some(DebrisStatus::Destroy);
             }
             return Some(DebrisStatus::Normal);
         }
-        self.clear_current();
+        self.clear_current(buffer);
         Some(DebrisStatus::Destroy)
     }
 
-    fn clear_current(&self) {
-        plot(' ', self.col, self.row, ColorCode::new(Color::Black, Color::Black));
+    fn clear_current(&self, buffer: &mut Buffer) {
+        buffer[self.row][self.col] = blank_cell();
     }
 
     fn update_location(&mut self) {
@@ -390,14 +1009,62 @@ impl Debris {
         }
     }
 
-    fn draw_current(&self, player: Player) {
+    fn draw_current(&self, player: Player, buffer: &mut Buffer) {
         if player.game_status == GameStatus::GameRunning {
-            plot(
-                '*',
-                self.col,
-                self.row,
-                ColorCode::new(self.color, Color::Black),
-            );
+            buffer[self.row][self.col] = ('*', ColorCode::new(self.color, Color::Black));
         }
     }
 }
+
+impl Projectile {
+    fn tick(&mut self, buffer: &mut Buffer) -> bool {
+        self.clear_current(buffer);
+        if self.col >= BUFFER_WIDTH - 1 {
+            return false;
+        }
+        if self.dx_tick == 0 {
+            self.col += 1;
+        } else {
+            self.dx_tick -= 1;
+        }
+        self.draw_current(buffer);
+        true
+    }
+
+    fn clear_current(&self, buffer: &mut Buffer) {
+        buffer[self.row][self.col] = blank_cell();
+    }
+
+    fn draw_current(&self, buffer: &mut Buffer) {
+        buffer[self.row][self.col] = ('-', ColorCode::new(Color::Yellow, Color::Black));
+    }
+}
+
+impl Caret {
+    fn new(col: usize, row: usize, kind: CaretKind) -> Self {
+        Self { col, row, frame: 0, kind }
+    }
+
+    fn frames(&self) -> [(char, Color); 4] {
+        match self.kind {
+            CaretKind::DebrisDestroyed => DESTROY_CARET_FRAMES,
+            CaretKind::PlayerCollision => COLLISION_CARET_FRAMES
+        }
+    }
+
+    fn tick(&mut self, buffer: &mut Buffer) -> bool {
+        self.clear_current(buffer);
+        let frames = self.frames();
+        if self.frame >= frames.len() {
+            return false;
+        }
+        let (glyph, color) = frames[self.frame];
+        buffer[self.row][self.col] = (glyph, ColorCode::new(color, Color::Black));
+        self.frame += 1;
+        true
+    }
+
+    fn clear_current(&self, buffer: &mut Buffer) {
+        buffer[self.row][self.col] = blank_cell();
+    }
+}